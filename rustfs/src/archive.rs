@@ -0,0 +1,283 @@
+use axum::{body::Body, extract::State, http::StatusCode, Json};
+use flate2::read::GzDecoder;
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use tar::Archive;
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt, BufWriter},
+};
+use tokio_util::io::StreamReader;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Subdirectory of `data_dir` that extracted archives are served from.
+const DEPLOY_SUBDIR: &str = "deploy";
+
+#[derive(Serialize)]
+pub struct DeployResponse {
+    success: bool,
+    written: Vec<String>,
+}
+
+/// `POST /deploy`: accepts a gzipped tarball as the raw request body and
+/// extracts it into `data_dir/deploy`, returning the relative paths written.
+pub async fn deploy_file(
+    State(state): State<Arc<AppState>>,
+    body: Body,
+) -> Result<Json<DeployResponse>, StatusCode> {
+    let deploy_root = state.data_dir.join(DEPLOY_SUBDIR);
+    fs::create_dir_all(&deploy_root)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let staging_path = deploy_root.join(format!("{}.tar.gz.part", Uuid::new_v4()));
+
+    let stream = body
+        .into_data_stream()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e));
+    let mut reader = StreamReader::new(stream);
+    let mut staged = BufWriter::new(
+        fs::File::create(&staging_path)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+
+    // Mirror upload_file's own cap enforcement: raw Body/Request extraction
+    // bypasses axum's DefaultBodyLimit layer entirely, so nothing else here
+    // bounds the size of the staged archive.
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut size: u64 = 0;
+    loop {
+        let n = match reader.read(&mut buf).await {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => {
+                error!("Error buffering archive body: {}", e);
+                let _ = fs::remove_file(&staging_path).await;
+                return Err(StatusCode::BAD_REQUEST);
+            }
+        };
+
+        size += n as u64;
+        if size > state.max_upload_bytes {
+            let _ = fs::remove_file(&staging_path).await;
+            warn!(
+                "Archive upload exceeded max size ({} > {} bytes), aborting",
+                size, state.max_upload_bytes
+            );
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        if let Err(e) = staged.write_all(&buf[..n]).await {
+            error!("Error writing staged archive: {}", e);
+            let _ = fs::remove_file(&staging_path).await;
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    if let Err(e) = staged.flush().await {
+        error!("Error flushing staged archive: {}", e);
+        let _ = fs::remove_file(&staging_path).await;
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    drop(staged);
+
+    let extract_root = deploy_root.clone();
+    let written = tokio::task::spawn_blocking(move || extract_tarball(&staging_path, &extract_root))
+        .await
+        .map_err(|e| {
+            error!("Archive extraction task panicked: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map_err(|e| {
+            error!("Error extracting archive: {}", e);
+            StatusCode::BAD_REQUEST
+        })?;
+
+    info!("Deployed {} file(s) to {:?}", written.len(), deploy_root);
+
+    Ok(Json(DeployResponse {
+        success: true,
+        written,
+    }))
+}
+
+/// Extracts the gzipped tarball at `archive_path` into `dest_dir`, rejecting
+/// any entry whose path is absolute or escapes `dest_dir` via `..`. The
+/// staged archive is removed once extraction finishes (successfully or not).
+fn extract_tarball(archive_path: &Path, dest_dir: &Path) -> io::Result<Vec<String>> {
+    let result = (|| {
+        let file = std::fs::File::open(archive_path)?;
+        let mut archive = Archive::new(GzDecoder::new(file));
+
+        let mut written = Vec::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+
+            let relative_path = match normalize_entry_path(&entry_path) {
+                Some(path) => path,
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("rejected unsafe archive entry path: {:?}", entry_path),
+                    ))
+                }
+            };
+
+            // An entry for "." itself (the archive root, e.g. from
+            // `tar -czf bundle.tar.gz -C dist .`) normalizes to an empty
+            // path — there's nothing to create or unpack.
+            if relative_path.as_os_str().is_empty() {
+                continue;
+            }
+
+            // Symlinks/hardlinks are rejected outright: a safe-looking path
+            // like "link/payload.txt" can still escape dest_dir if an
+            // earlier entry planted "link" as a symlink pointing elsewhere
+            // (tar-slip), since entries are unpacked one at a time rather
+            // than through tar's own dest_dir-aware Archive::unpack.
+            let entry_type = entry.header().entry_type();
+            if !(entry_type.is_file() || entry_type.is_dir()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "rejected archive entry with unsupported type {:?}: {:?}",
+                        entry_type, entry_path
+                    ),
+                ));
+            }
+
+            let dest_path = dest_dir.join(&relative_path);
+            if let Some(parent) = dest_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry.unpack(&dest_path)?;
+            written.push(relative_path.to_string_lossy().into_owned());
+        }
+
+        Ok(written)
+    })();
+
+    let _ = std::fs::remove_file(archive_path);
+    result
+}
+
+/// Normalizes an archive entry path, dropping `.` (`Component::CurDir`)
+/// components — standard tarballs (`tar -czf bundle.tar.gz -C dist .`)
+/// prefix every entry with `./` — and rejecting anything absolute or
+/// containing `..`. Returns `None` for unsafe paths.
+fn normalize_entry_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => continue,
+            Component::Normal(part) => normalized.push(part),
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use tar::{Builder, Header};
+
+    fn build_tarball_with_file(path: &str, contents: &[u8]) -> Vec<u8> {
+        let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut header = Header::new_gnu();
+        // Write the name field directly: `Header::set_path` refuses absolute
+        // and `..` paths and strips a leading "./", so it could never build
+        // the entries these tests need to feed to extract_tarball.
+        let name = &mut header.as_old_mut().name;
+        name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_size(contents.len() as u64);
+        header.set_cksum();
+        builder.append(&header, contents).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn build_tarball_with_symlink(path: &str, target: &str) -> Vec<u8> {
+        let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut header = Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_cksum();
+        builder.append_link(&mut header, path, target).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn extract_bytes(bytes: &[u8]) -> io::Result<Vec<String>> {
+        let dir = std::env::temp_dir().join(format!("rustfs-archive-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive_path = dir.join("bundle.tar.gz");
+        std::fs::write(&archive_path, bytes).unwrap();
+
+        let dest_dir = dir.join("dest");
+        std::fs::create_dir_all(&dest_dir).unwrap();
+        let result = extract_tarball(&archive_path, &dest_dir);
+
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+
+    #[test]
+    fn normalize_entry_path_drops_leading_curdir() {
+        // Standard `tar -czf bundle.tar.gz -C dist .` invocations prefix
+        // every entry with "./".
+        assert_eq!(
+            normalize_entry_path(Path::new("./foo/bar")),
+            Some(PathBuf::from("foo/bar"))
+        );
+    }
+
+    #[test]
+    fn normalize_entry_path_treats_archive_root_as_empty() {
+        assert_eq!(normalize_entry_path(Path::new(".")), Some(PathBuf::new()));
+    }
+
+    #[test]
+    fn normalize_entry_path_rejects_parent_dir_traversal() {
+        assert_eq!(normalize_entry_path(Path::new("../escape")), None);
+        assert_eq!(normalize_entry_path(Path::new("foo/../../escape")), None);
+    }
+
+    #[test]
+    fn normalize_entry_path_rejects_absolute_paths() {
+        assert_eq!(normalize_entry_path(Path::new("/etc/passwd")), None);
+    }
+
+    #[test]
+    fn extract_tarball_writes_curdir_prefixed_entries() {
+        let bytes = build_tarball_with_file("./index.html", b"hello");
+        let written = extract_bytes(&bytes).expect("standard tarball should extract cleanly");
+        assert_eq!(written, vec!["index.html".to_string()]);
+    }
+
+    #[test]
+    fn extract_tarball_rejects_parent_dir_traversal() {
+        let bytes = build_tarball_with_file("../escape.txt", b"pwned");
+        assert!(extract_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn extract_tarball_rejects_absolute_paths() {
+        let bytes = build_tarball_with_file("/etc/passwd", b"pwned");
+        assert!(extract_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn extract_tarball_rejects_symlink_entries() {
+        let bytes = build_tarball_with_symlink("link", "/etc");
+        assert!(extract_bytes(&bytes).is_err());
+    }
+}
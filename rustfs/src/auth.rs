@@ -0,0 +1,199 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use hmac::{Hmac, Mac};
+use jwt::VerifyWithKey;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    env,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use subtle::ConstantTimeEq;
+
+use crate::AppState;
+
+/// Trust model for validating inbound bearer tokens on write/delete routes.
+///
+/// Deployments pick one via environment variables at startup; see
+/// [`AuthConfig::from_env`].
+#[derive(Clone)]
+pub enum AuthConfig {
+    /// No credential configured — write/delete routes are left open.
+    Disabled,
+    /// A single static shared secret compared against the `Authorization` header.
+    StaticToken(String),
+    /// HMAC-SHA256-signed JWTs; the `exp` claim is enforced.
+    Jwt { secret: String },
+}
+
+impl AuthConfig {
+    /// Builds the auth config from the environment.
+    ///
+    /// `RUSTFS_JWT_SECRET` takes precedence over `RUSTFS_AUTH_TOKEN`; if
+    /// neither is set, auth is disabled (the pre-existing behavior).
+    pub fn from_env() -> Self {
+        if let Ok(secret) = env::var("RUSTFS_JWT_SECRET") {
+            AuthConfig::Jwt { secret }
+        } else if let Ok(token) = env::var("RUSTFS_AUTH_TOKEN") {
+            AuthConfig::StaticToken(token)
+        } else {
+            AuthConfig::Disabled
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    exp: u64,
+}
+
+fn verify_jwt(token: &str, secret: &str) -> bool {
+    let key: Hmac<Sha256> = match Hmac::new_from_slice(secret.as_bytes()) {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+
+    let claims: Claims = match token.verify_with_key(&key) {
+        Ok(claims) => claims,
+        Err(_) => return false,
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    claims.exp > now
+}
+
+fn bearer_token(req: &Request) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+/// Constant-time comparison so a mismatching byte position in a static
+/// token can't leak through response-timing side channels.
+fn tokens_match(token: &str, expected: &str) -> bool {
+    token.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Tower middleware guarding write/delete routes with `Authorization: Bearer <token>`.
+///
+/// `/health` and the read routes are never wrapped with this, regardless of
+/// which [`AuthConfig`] variant is active.
+pub async fn require_bearer_auth(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    match &state.auth {
+        AuthConfig::Disabled => Ok(next.run(req).await),
+        AuthConfig::StaticToken(expected) => {
+            let token = bearer_token(&req).ok_or(StatusCode::UNAUTHORIZED)?;
+            if tokens_match(token, expected) {
+                Ok(next.run(req).await)
+            } else {
+                Err(StatusCode::UNAUTHORIZED)
+            }
+        }
+        AuthConfig::Jwt { secret } => {
+            let token = bearer_token(&req).ok_or(StatusCode::UNAUTHORIZED)?;
+            if verify_jwt(token, secret) {
+                Ok(next.run(req).await)
+            } else {
+                Err(StatusCode::UNAUTHORIZED)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use jwt::SignWithKey;
+
+    #[derive(Serialize)]
+    struct SignableClaims {
+        exp: u64,
+    }
+
+    fn sign(secret: &str, exp: u64) -> String {
+        let key: Hmac<Sha256> = Hmac::new_from_slice(secret.as_bytes()).unwrap();
+        SignableClaims { exp }.sign_with_key(&key).unwrap()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn request_with_header(value: Option<&str>) -> Request {
+        let mut builder = axum::http::Request::builder();
+        if let Some(value) = value {
+            builder = builder.header(header::AUTHORIZATION, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn bearer_token_extracts_token_from_valid_header() {
+        let req = request_with_header(Some("Bearer my-token"));
+        assert_eq!(bearer_token(&req), Some("my-token"));
+    }
+
+    #[test]
+    fn bearer_token_rejects_missing_header() {
+        let req = request_with_header(None);
+        assert_eq!(bearer_token(&req), None);
+    }
+
+    #[test]
+    fn bearer_token_rejects_non_bearer_scheme() {
+        let req = request_with_header(Some("Basic my-token"));
+        assert_eq!(bearer_token(&req), None);
+    }
+
+    #[test]
+    fn tokens_match_accepts_equal_tokens() {
+        assert!(tokens_match("secret", "secret"));
+    }
+
+    #[test]
+    fn tokens_match_rejects_different_tokens() {
+        assert!(!tokens_match("secret", "not-secret"));
+        assert!(!tokens_match("short", "much-longer-secret"));
+    }
+
+    #[test]
+    fn verify_jwt_accepts_valid_unexpired_token() {
+        let token = sign("top-secret", now() + 3600);
+        assert!(verify_jwt(&token, "top-secret"));
+    }
+
+    #[test]
+    fn verify_jwt_rejects_expired_token() {
+        let token = sign("top-secret", now().saturating_sub(3600));
+        assert!(!verify_jwt(&token, "top-secret"));
+    }
+
+    #[test]
+    fn verify_jwt_rejects_wrong_secret() {
+        let token = sign("top-secret", now() + 3600);
+        assert!(!verify_jwt(&token, "wrong-secret"));
+    }
+
+    #[test]
+    fn verify_jwt_rejects_malformed_token() {
+        assert!(!verify_jwt("not-a-jwt", "top-secret"));
+    }
+}
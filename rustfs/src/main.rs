@@ -1,6 +1,8 @@
 use axum::{
-    extract::{Multipart, Path, State},
-    http::{header, StatusCode},
+    body::Body,
+    extract::{DefaultBodyLimit, Multipart, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware,
     response::{IntoResponse, Response},
     routing::{delete, get, post},
     Json, Router,
@@ -8,14 +10,45 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::{env, path::PathBuf, sync::Arc};
-use tokio::{fs, io::AsyncWriteExt};
+use tokio::{
+    fs,
+    io::{AsyncSeekExt, AsyncWriteExt},
+};
+use tokio_util::io::ReaderStream;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-#[derive(Clone)]
-struct AppState {
+mod archive;
+mod auth;
+mod store;
+
+use archive::deploy_file;
+use auth::AuthConfig;
+use store::{FileRecord, MetadataStore};
+
+/// Default cap on a single upload's size when `RUSTFS_MAX_UPLOAD_BYTES` is unset (1 GiB).
+const DEFAULT_MAX_UPLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+pub(crate) struct AppState {
     data_dir: PathBuf,
+    max_upload_bytes: u64,
+    store: MetadataStore,
+    auth: AuthConfig,
+    dedup: bool,
+    cache_max_age: Option<u64>,
+}
+
+/// Resolves the on-disk path of the blob backing `id`, honoring whichever
+/// storage scheme was in effect when it was uploaded (see
+/// [`FileRecord::content_addressed`]).
+fn blob_path(state: &AppState, id: &str, record: &FileRecord) -> PathBuf {
+    if record.content_addressed {
+        let hash = &record.content_hash;
+        state.data_dir.join(&hash[..2]).join(hash)
+    } else {
+        state.data_dir.join(&id[..2]).join(id)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -41,6 +74,10 @@ async fn main() {
     // Get data directory from environment
     let data_dir = env::var("RUSTFS_DATA_DIR").unwrap_or_else(|_| "/data".to_string());
     let port = env::var("RUSTFS_PORT").unwrap_or_else(|_| "9000".to_string());
+    let max_upload_bytes = env::var("RUSTFS_MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES);
 
     let data_path = PathBuf::from(data_dir);
 
@@ -51,16 +88,56 @@ async fn main() {
 
     info!("RustFS starting on port {}", port);
     info!("Data directory: {:?}", data_path);
+    info!("Max upload size: {} bytes", max_upload_bytes);
+
+    let store = MetadataStore::open(data_path.join(".rustfs-index"))
+        .expect("Failed to open metadata store");
+
+    let auth = AuthConfig::from_env();
+    match &auth {
+        AuthConfig::Disabled => warn!("No RUSTFS_AUTH_TOKEN or RUSTFS_JWT_SECRET set; write/delete routes are unauthenticated"),
+        AuthConfig::StaticToken(_) => info!("Bearer auth enabled (static token)"),
+        AuthConfig::Jwt { .. } => info!("Bearer auth enabled (HMAC-signed JWT)"),
+    }
+
+    let dedup = env::var("RUSTFS_DEDUP")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if dedup {
+        info!("Content-addressed deduplication enabled");
+    }
+
+    let cache_max_age = env::var("RUSTFS_CACHE_MAX_AGE").ok().and_then(|v| v.parse().ok());
 
     let state = Arc::new(AppState {
         data_dir: data_path,
+        max_upload_bytes,
+        store,
+        auth,
+        dedup,
+        cache_max_age,
     });
 
+    // Write/delete routes require a bearer token (when configured); reads and
+    // /health stay open.
+    let protected = Router::new()
+        .route("/upload", post(upload_file))
+        .route("/deploy", post(deploy_file))
+        .route("/files/:id", delete(delete_file))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_bearer_auth,
+        ));
+
     let app = Router::new()
         .route("/health", get(health_check))
-        .route("/upload", post(upload_file))
-        .route("/files/:id", get(download_file).delete(delete_file))
+        .route("/files/:id", get(download_file))
         .route("/files/:id/info", get(file_info))
+        .merge(protected)
+        // axum's Multipart/body extractors cap requests at 2 MB by default;
+        // raise that to the same limit we enforce ourselves so uploads up to
+        // RUSTFS_MAX_UPLOAD_BYTES actually reach upload_file's streaming loop.
+        .layer(DefaultBodyLimit::max(max_upload_bytes as usize))
         .layer(CorsLayer::permissive())
         .with_state(state);
 
@@ -81,51 +158,144 @@ async fn health_check() -> impl IntoResponse {
     }))
 }
 
+/// Falls back to a best-effort guess from the filename extension when a
+/// multipart field doesn't declare a `Content-Type`.
+fn sniff_content_type(filename: &str) -> String {
+    mime_guess::from_path(filename)
+        .first_raw()
+        .unwrap_or("application/octet-stream")
+        .to_string()
+}
+
 async fn upload_file(
     State(state): State<Arc<AppState>>,
     mut multipart: Multipart,
 ) -> Result<Json<UploadResponse>, StatusCode> {
     let mut filename = String::new();
-    let mut file_data = Vec::new();
+    let mut content_type = String::new();
+    let mut wrote_field = false;
+
+    // Generate file ID up front; the bytes land in a staging file first since
+    // where the blob ultimately lives depends on its content hash (dedup
+    // mode) which isn't known until the upload finishes streaming.
+    let file_id = Uuid::new_v4().to_string();
+    let prefix = &file_id[..2];
+    let dir_path = state.data_dir.join(prefix);
+    fs::create_dir_all(&dir_path)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let staging_path = dir_path.join(format!("{}.part", file_id));
 
-    while let Some(field) = multipart.next_field().await.map_err(|_| StatusCode::BAD_REQUEST)? {
+    let mut hasher = Sha256::new();
+    let mut size: u64 = 0;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(|_| StatusCode::BAD_REQUEST)? {
         let name = field.name().unwrap_or("").to_string();
 
-        if name == "file" {
-            filename = field
-                .file_name()
-                .unwrap_or("unnamed")
-                .to_string();
-            file_data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?.to_vec();
+        if name != "file" {
+            continue;
+        }
+
+        filename = field.file_name().unwrap_or("unnamed").to_string();
+        content_type = field
+            .content_type()
+            .map(|ct| ct.to_string())
+            .unwrap_or_else(|| sniff_content_type(&filename));
+        wrote_field = true;
+
+        let mut file = fs::File::create(&staging_path)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        loop {
+            let chunk = match field.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(_) => {
+                    drop(file);
+                    let _ = fs::remove_file(&staging_path).await;
+                    return Err(StatusCode::BAD_REQUEST);
+                }
+            };
+
+            size += chunk.len() as u64;
+            if size > state.max_upload_bytes {
+                drop(file);
+                let _ = fs::remove_file(&staging_path).await;
+                warn!(
+                    "Upload exceeded max size ({} > {} bytes), aborting",
+                    size, state.max_upload_bytes
+                );
+                return Err(StatusCode::PAYLOAD_TOO_LARGE);
+            }
+
+            hasher.update(&chunk);
+            file.write_all(&chunk)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
         }
     }
 
-    if file_data.is_empty() {
+    if !wrote_field || size == 0 {
+        let _ = fs::remove_file(&staging_path).await;
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    // Generate file ID and hash
-    let file_id = Uuid::new_v4().to_string();
-    let mut hasher = Sha256::new();
-    hasher.update(&file_data);
     let content_hash = hex::encode(hasher.finalize());
 
-    // Create directory structure: data/{first_two_chars}/{file_id}
-    let prefix = &file_id[..2];
-    let dir_path = state.data_dir.join(prefix);
-    fs::create_dir_all(&dir_path)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let file_path = dir_path.join(&file_id);
-
-    // Write file
-    let mut file = fs::File::create(&file_path)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    file.write_all(&file_data)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let content_addressed = if state.dedup {
+        // Serialize "check refcount, then rename-or-skip the blob" per hash:
+        // without this, two uploads of identical content racing incr_hash_ref
+        // can both observe ref_count > 1 and both discard their staged copy,
+        // leaving the refcount live with no blob ever written.
+        let lock = state.store.hash_lock(&content_hash);
+        let _guard = lock.lock().await;
+
+        let hash_dir = state.data_dir.join(&content_hash[..2]);
+        fs::create_dir_all(&hash_dir)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let blob_path = hash_dir.join(&content_hash);
+
+        let ref_count = state.store.incr_hash_ref(&content_hash).map_err(|e| {
+            error!("Error updating hash refcount: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        if ref_count > 1 {
+            // Identical content already stored; drop the redundant copy.
+            let _ = fs::remove_file(&staging_path).await;
+        } else if let Err(e) = fs::rename(&staging_path, &blob_path).await {
+            // We're the first (and only) holder of this hash's refcount, so
+            // a failed write here must roll the counter back — otherwise
+            // it's stuck at 1 forever with no blob behind it, and every
+            // later upload of the same content would wrongly assume the
+            // blob already exists and discard its own copy too.
+            error!("Error writing content-addressed blob: {}", e);
+            let _ = state.store.decr_hash_ref(&content_hash);
+            let _ = fs::remove_file(&staging_path).await;
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        true
+    } else {
+        let file_path = dir_path.join(&file_id);
+        fs::rename(&staging_path, &file_path)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        false
+    };
+
+    let record = FileRecord::new(
+        filename.clone(),
+        size,
+        content_hash.clone(),
+        content_type,
+        content_addressed,
+    );
+    state.store.insert(&file_id, &record).map_err(|e| {
+        error!("Error writing metadata: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
 
     info!("File uploaded: {} ({})", filename, file_id);
 
@@ -134,45 +304,312 @@ async fn upload_file(
         file: FileInfo {
             id: file_id.clone(),
             filename,
-            size: file_data.len() as u64,
+            size,
             content_hash,
             path: format!("/files/{}", file_id),
         },
     }))
 }
 
+/// A single byte range parsed out of a `Range: bytes=start-end` header.
+struct ByteRange {
+    start: u64,
+    end: u64,
+}
+
+/// Parses a single-range `Range: bytes=start-end` header against a file of `total` bytes.
+///
+/// Returns `Ok(None)` when there's no (or a malformed, multi-range) header, which callers
+/// should treat as "serve the full body". Returns `Err(())` when the header is well-formed
+/// but unsatisfiable (`start >= total`), which callers should turn into a 416.
+fn parse_range(headers: &HeaderMap, total: u64) -> Result<Option<ByteRange>, ()> {
+    let raw = match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
+
+    let spec = match raw.strip_prefix("bytes=") {
+        Some(spec) if !spec.contains(',') => spec,
+        _ => return Ok(None),
+    };
+
+    let (start_str, end_str) = match spec.split_once('-') {
+        Some(parts) => parts,
+        None => return Ok(None),
+    };
+
+    let (start, end) = if start_str.is_empty() {
+        // Suffix range: `bytes=-N` means the last N bytes.
+        let suffix_len: u64 = match end_str.parse() {
+            Ok(n) => n,
+            Err(_) => return Ok(None),
+        };
+        if suffix_len == 0 || total == 0 {
+            return Err(());
+        }
+        let start = total.saturating_sub(suffix_len);
+        (start, total - 1)
+    } else {
+        let start: u64 = match start_str.parse() {
+            Ok(n) => n,
+            Err(_) => return Ok(None),
+        };
+        let end = if end_str.is_empty() {
+            total.saturating_sub(1)
+        } else {
+            match end_str.parse() {
+                Ok(n) => n,
+                Err(_) => return Ok(None),
+            }
+        };
+        (start, end)
+    };
+
+    if start > end {
+        return Ok(None);
+    }
+
+    if start >= total {
+        return Err(());
+    }
+
+    Ok(Some(ByteRange {
+        start,
+        end: end.min(total.saturating_sub(1)),
+    }))
+}
+
+/// Builds the strong `ETag` for a stored file from its content hash.
+fn etag_for(content_hash: &str) -> String {
+    format!("\"{}\"", content_hash)
+}
+
+/// Sanitizes a stored filename for embedding in a `Content-Disposition`
+/// quoted-string value: control characters (including CR/LF/NUL) are
+/// dropped outright — none are legal in an HTTP header value, and letting
+/// one through would make axum fail to build the header and return a 500
+/// on every future download — and embedded backslashes/quotes are escaped
+/// per RFC 6266 so a filename like `my "file".txt` doesn't break the
+/// quoted-string syntax.
+fn escape_content_disposition_filename(filename: &str) -> String {
+    filename
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+/// Returns `true` if the request's `If-None-Match` or `If-Modified-Since`
+/// headers indicate the client's cached copy is still fresh.
+///
+/// `If-None-Match` takes precedence over `If-Modified-Since` when both are
+/// present, per RFC 9110 §13.1.2.
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: std::time::SystemTime) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        return if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|candidate| candidate == "*" || candidate == etag);
+    }
+
+    if let Some(if_modified_since) = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+    {
+        if let Ok(since) = httpdate::parse_http_date(if_modified_since) {
+            return last_modified <= since;
+        }
+    }
+
+    false
+}
+
+/// Truncates a `SystemTime` down to whole-second precision by round-tripping
+/// it through the HTTP-date format, matching the precision `httpdate`
+/// produces when parsing `If-Modified-Since`. Without this, comparing a
+/// sub-second filesystem mtime against a second-granular `since` makes
+/// `last_modified <= since` true only on the rare exact-second match.
+fn truncate_to_http_date_precision(t: std::time::SystemTime) -> std::time::SystemTime {
+    httpdate::parse_http_date(&httpdate::fmt_http_date(t)).unwrap_or(t)
+}
+
+/// Caching headers (`ETag`, `Last-Modified`, and an optional `Cache-Control`)
+/// shared by `download_file` and `file_info` responses.
+fn cache_headers(state: &AppState, etag: &str, last_modified: std::time::SystemTime) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::ETAG,
+        etag.parse().expect("etag is a valid header value"),
+    );
+    headers.insert(
+        header::LAST_MODIFIED,
+        httpdate::fmt_http_date(last_modified)
+            .parse()
+            .expect("http-date is a valid header value"),
+    );
+    if let Some(max_age) = state.cache_max_age {
+        headers.insert(
+            header::CACHE_CONTROL,
+            format!("max-age={}", max_age)
+                .parse()
+                .expect("cache-control is a valid header value"),
+        );
+    }
+    headers
+}
+
 async fn download_file(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Response, StatusCode> {
-    let prefix = &id[..2];
-    let file_path = state.data_dir.join(prefix).join(&id);
+    let record = state
+        .store
+        .get(&id)
+        .map_err(|e| {
+            error!("Error reading metadata: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let file_path = blob_path(&state, &id, &record);
 
     if !file_path.exists() {
         return Err(StatusCode::NOT_FOUND);
     }
 
-    let data = fs::read(&file_path).await.map_err(|e| {
-        error!("Error reading file: {}", e);
+    let metadata = fs::metadata(&file_path)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let total = metadata.len();
+    let content_disposition = format!(
+        "attachment; filename=\"{}\"",
+        escape_content_disposition_filename(&record.filename)
+    );
+
+    let etag = etag_for(&record.content_hash);
+    let last_modified = truncate_to_http_date_precision(
+        metadata
+            .modified()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+    let mut response_headers = cache_headers(&state, &etag, last_modified);
+
+    if is_not_modified(&headers, &etag, last_modified) {
+        response_headers.insert(header::ACCEPT_RANGES, "bytes".parse().unwrap());
+        return Ok((StatusCode::NOT_MODIFIED, response_headers).into_response());
+    }
+
+    let range = match parse_range(&headers, total) {
+        Ok(range) => range,
+        Err(()) => {
+            return Ok((
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [
+                    (header::CONTENT_RANGE, format!("bytes */{}", total)),
+                    (header::ACCEPT_RANGES, "bytes".to_string()),
+                ],
+            )
+                .into_response())
+        }
+    };
+
+    let mut file = fs::File::open(&file_path).await.map_err(|e| {
+        error!("Error opening file: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    Ok(([(header::CONTENT_TYPE, "application/octet-stream")], data).into_response())
+    match range {
+        Some(ByteRange { start, end }) => {
+            let len = end - start + 1;
+            file.seek(std::io::SeekFrom::Start(start))
+                .await
+                .map_err(|e| {
+                    error!("Error seeking file: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            let body = Body::from_stream(ReaderStream::new(file.take(len)));
+
+            let mut response = (
+                StatusCode::PARTIAL_CONTENT,
+                [
+                    (header::CONTENT_TYPE, record.content_type.clone()),
+                    (header::CONTENT_LENGTH, len.to_string()),
+                    (
+                        header::CONTENT_RANGE,
+                        format!("bytes {}-{}/{}", start, end, total),
+                    ),
+                    (header::ACCEPT_RANGES, "bytes".to_string()),
+                    (header::CONTENT_DISPOSITION, content_disposition),
+                ],
+                body,
+            )
+                .into_response();
+            response.headers_mut().extend(response_headers);
+            Ok(response)
+        }
+        None => {
+            let body = Body::from_stream(ReaderStream::new(file));
+
+            let mut response = (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, record.content_type.clone()),
+                    (header::CONTENT_LENGTH, total.to_string()),
+                    (header::ACCEPT_RANGES, "bytes".to_string()),
+                    (header::CONTENT_DISPOSITION, content_disposition),
+                ],
+                body,
+            )
+                .into_response();
+            response.headers_mut().extend(response_headers);
+            Ok(response)
+        }
+    }
 }
 
 async fn delete_file(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
-    let prefix = &id[..2];
-    let file_path = state.data_dir.join(prefix).join(&id);
-
-    if !file_path.exists() {
-        return Err(StatusCode::NOT_FOUND);
+    let record = state
+        .store
+        .get(&id)
+        .map_err(|e| {
+            error!("Error reading metadata: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let file_path = blob_path(&state, &id, &record);
+
+    if record.content_addressed {
+        // Same per-hash lock upload_file takes around its refcount bump +
+        // rename, so a delete can't drop the blob out from under a
+        // concurrent upload that just incremented the same hash's refcount.
+        let lock = state.store.hash_lock(&record.content_hash);
+        let _guard = lock.lock().await;
+
+        let remaining = state.store.decr_hash_ref(&record.content_hash).map_err(|e| {
+            error!("Error updating hash refcount: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        if remaining == 0 {
+            fs::remove_file(&file_path).await.map_err(|e| {
+                error!("Error deleting file: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        }
+    } else {
+        fs::remove_file(&file_path).await.map_err(|e| {
+            error!("Error deleting file: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
     }
 
-    fs::remove_file(&file_path).await.map_err(|e| {
-        error!("Error deleting file: {}", e);
+    state.store.remove(&id).map_err(|e| {
+        error!("Error removing metadata: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
@@ -184,30 +621,33 @@ async fn delete_file(
 async fn file_info(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> Result<Json<FileInfo>, StatusCode> {
-    let prefix = &id[..2];
-    let file_path = state.data_dir.join(prefix).join(&id);
-
-    if !file_path.exists() {
-        return Err(StatusCode::NOT_FOUND);
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let record = state
+        .store
+        .get(&id)
+        .map_err(|e| {
+            error!("Error reading metadata: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let etag = etag_for(&record.content_hash);
+    let last_modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(record.uploaded_at);
+    let response_headers = cache_headers(&state, &etag, last_modified);
+
+    if is_not_modified(&headers, &etag, last_modified) {
+        return Ok((StatusCode::NOT_MODIFIED, response_headers).into_response());
     }
 
-    let metadata = fs::metadata(&file_path)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let data = fs::read(&file_path)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let mut hasher = Sha256::new();
-    hasher.update(&data);
-    let content_hash = hex::encode(hasher.finalize());
-
-    Ok(Json(FileInfo {
+    let mut response = Json(FileInfo {
         id: id.clone(),
-        filename: id.clone(),
-        size: metadata.len(),
-        content_hash,
+        filename: record.filename,
+        size: record.size,
+        content_hash: record.content_hash,
         path: format!("/files/{}", id),
-    }))
+    })
+    .into_response();
+    response.headers_mut().extend(response_headers);
+    Ok(response)
 }
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex as SyncMutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// Metadata captured for every stored file, persisted in a `sled` tree so
+/// `file_info` and friends don't need to re-read (and re-hash) the blob on
+/// every call.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileRecord {
+    pub filename: String,
+    pub size: u64,
+    pub content_hash: String,
+    pub content_type: String,
+    pub uploaded_at: u64,
+    /// Whether the blob backing this id lives at a content-addressed path
+    /// (`data/{hash[..2]}/{hash}`, shared with other ids that hash the same)
+    /// rather than the legacy per-id path (`data/{id[..2]}/{id}`).
+    #[serde(default)]
+    pub content_addressed: bool,
+}
+
+impl FileRecord {
+    pub fn new(
+        filename: String,
+        size: u64,
+        content_hash: String,
+        content_type: String,
+        content_addressed: bool,
+    ) -> Self {
+        let uploaded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            filename,
+            size,
+            content_hash,
+            content_type,
+            uploaded_at,
+            content_addressed,
+        }
+    }
+}
+
+/// Embedded sled-backed index of file metadata, keyed by file id.
+///
+/// This turns `FileInfo` lookups into a single keyed index read and preserves data
+/// (original filename, content type) that would otherwise be lost once the
+/// file is on disk under its generated id.
+#[derive(Clone)]
+pub struct MetadataStore {
+    db: sled::Db,
+    /// Reference counts for content-addressed blobs, keyed by hash.
+    hash_refs: sled::Tree,
+    /// Per-hash locks serializing the "bump refcount, then write/rename the
+    /// blob" sequence in dedup mode, so two concurrent uploads of identical
+    /// content can't both see `ref_count > 1` and both skip writing the blob.
+    hash_locks: Arc<SyncMutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+}
+
+impl MetadataStore {
+    pub fn open(path: impl AsRef<Path>) -> sled::Result<Self> {
+        let db = sled::open(path)?;
+        let hash_refs = db.open_tree("hash_refs")?;
+        Ok(Self {
+            db,
+            hash_refs,
+            hash_locks: Arc::new(SyncMutex::new(HashMap::new())),
+        })
+    }
+
+    /// Returns the lock guarding dedup bookkeeping for `hash`. Callers should
+    /// hold it for the full "check refcount, then rename-or-skip the blob"
+    /// sequence. Entries for locks no longer held elsewhere are pruned on
+    /// each call so the map doesn't grow unbounded over the process lifetime.
+    pub fn hash_lock(&self, hash: &str) -> Arc<AsyncMutex<()>> {
+        let mut locks = self.hash_locks.lock().expect("hash_locks mutex poisoned");
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        locks
+            .entry(hash.to_string())
+            .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+            .clone()
+    }
+
+    pub fn insert(&self, id: &str, record: &FileRecord) -> sled::Result<()> {
+        let bytes = serde_json::to_vec(record).expect("FileRecord always serializes");
+        self.db.insert(id, bytes)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> sled::Result<Option<FileRecord>> {
+        Ok(self.db.get(id)?.map(|bytes| {
+            serde_json::from_slice(&bytes).expect("stored FileRecord always deserializes")
+        }))
+    }
+
+    pub fn remove(&self, id: &str) -> sled::Result<()> {
+        self.db.remove(id)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    /// Increments the refcount for `hash` and returns the count after the increment.
+    pub fn incr_hash_ref(&self, hash: &str) -> sled::Result<u64> {
+        let updated = self.hash_refs.update_and_fetch(hash, |old| {
+            let count = decode_count(old);
+            Some((count + 1).to_be_bytes().to_vec())
+        })?;
+        self.hash_refs.flush()?;
+        Ok(decode_count(updated.as_deref()))
+    }
+
+    /// Decrements the refcount for `hash`, removing the entry once it reaches
+    /// zero. Returns the count after the decrement.
+    pub fn decr_hash_ref(&self, hash: &str) -> sled::Result<u64> {
+        let updated = self.hash_refs.update_and_fetch(hash, |old| {
+            let count = decode_count(old).saturating_sub(1);
+            if count == 0 {
+                None
+            } else {
+                Some(count.to_be_bytes().to_vec())
+            }
+        })?;
+        self.hash_refs.flush()?;
+        Ok(decode_count(updated.as_deref()))
+    }
+}
+
+fn decode_count(bytes: Option<&[u8]>) -> u64 {
+    bytes
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_be_bytes)
+        .unwrap_or(0)
+}